@@ -1,8 +1,8 @@
 #![doc = include_str!("../README.md")]
 
-use std::{thread::sleep, time::Duration, fmt::Display, hash::Hash};
+use std::{fmt::Display, hash::Hash, rc::Rc, cell::RefCell};
 
-use rppal::i2c::I2c;
+use embedded_hal::i2c::I2c;
 
 const IODIRA: u8 = 0x00;
 const IODIRB: u8 = 0x01; 
@@ -25,14 +25,18 @@ const OLATA: u8 = 0x14;
 const OLATB: u8 = 0x15; 
 
 const IOCONMIRROR: Pin = Pin{pin: 6, orig: 6, shift: 0, bank: Bank::A };
+const IOCONSEQOP: Pin = Pin{pin: 5, orig: 5, shift: 0, bank: Bank::A };
+const IOCONDISSLW: Pin = Pin{pin: 4, orig: 4, shift: 0, bank: Bank::A };
+const IOCONHAEN: Pin = Pin{pin: 3, orig: 3, shift: 0, bank: Bank::A };
+const IOCONODR: Pin = Pin{pin: 2, orig: 2, shift: 0, bank: Bank::A };
 const IOCONINTPOL: Pin = Pin{pin: 1, orig: 1, shift: 0, bank: Bank::A };
 
 const NUM_GPIO: u8 = 16;
 
 #[derive(Debug)]
 /// MCP23017 Error
-pub enum Error<'a> { 
-    I2C(rppal::i2c::Error),
+pub enum Error<'a, E> {
+    I2C(E),
     WrongMode(&'a Pin),
     InterruptsForcedClear,
 }
@@ -298,26 +302,95 @@ impl Hash for Pin {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+/// Full IOCON configuration.
+///
+/// Covers every writeable IOCON bit except BANK so several expanders can be
+/// chained on a single INT line. Note that enabling `odr` (open-drain INT output) overrides
+/// `intpol`, as required for wired-OR interrupt lines.
+pub struct IoconConfig {
+    /// MIRROR - mirror the INTA and INTB pins together.
+    pub mirror: Feature,
+    /// ODR - open-drain INT output. Overrides `intpol` when on.
+    pub odr: Feature,
+    /// INTPOL - polarity of the INT pin.
+    pub intpol: State,
+    /// SEQOP - when on, disables the address auto-increment for byte-mode polling.
+    pub seqop: Feature,
+    /// DISSLW - when on, disables the SDA slew-rate control for fast I2C.
+    pub disslw: Feature,
+    /// HAEN - enable the hardware address pins.
+    pub haen: Feature,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Pending-interrupt flags read from INTFA/INTFB in a single poll.
+pub struct InterruptStatus {
+    /// Raw INTFA flag byte (bank A, pins 0-7).
+    pub bank_a: u8,
+    /// Raw INTFB flag byte (bank B, pins 8-15).
+    pub bank_b: u8,
+}
+
+impl InterruptStatus {
+    /// Whether any pin of either bank has a pending interrupt.
+    pub fn pending(&self) -> bool {
+        self.bank_a != 0 || self.bank_b != 0
+    }
+}
+
 /// MCP23017 i2c Interface
-pub struct MCP23017 {
-    i2c: I2c,
+pub struct MCP23017<I2C> {
+    i2c: I2C,
+    address: u8,
     direction: u16,
     mirrored: Feature,
 }
 
-impl MCP23017 {
-    pub fn new<'a>(address: u16, bus: u8) -> Result<MCP23017, Error<'a>> {
-        let mut i2c = I2c::with_bus(bus).map_err(|e| Error::I2C(e))?;
-        i2c.set_slave_address(address).map_err(|e| Error::I2C(e))?;
+impl<I2C, E> MCP23017<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub fn new<'a>(i2c: I2C, address: u8) -> Result<MCP23017<I2C>, Error<'a, E>> {
+        let mut mcp23017 = MCP23017 { i2c, address, direction: 0, mirrored: Feature::Off };
 
-        let mut direction = i2c.smbus_read_byte(IODIRA).map_err(|e| Error::I2C(e))? as u16; 
-        direction |= (i2c.smbus_read_byte(IODIRB).map_err(|e| Error::I2C(e))? as u16) << 8;
+        let mut direction = mcp23017.read_register(IODIRA)? as u16;
+        direction |= (mcp23017.read_register(IODIRB)? as u16) << 8;
+        mcp23017.direction = direction;
 
-        let mcp23017 = MCP23017 { i2c, direction,  mirrored: Feature::Off};
         mcp23017.reset()?;
         Ok(mcp23017)
     }
 
+    /// Read a single register over the bus.
+    fn read_register<'a>(&mut self, register: u8) -> Result<u8, Error<'a, E>> {
+        let mut buf = [0u8];
+        self.i2c.write_read(self.address, &[register], &mut buf).map_err(|e| Error::I2C(e))?;
+        Ok(buf[0])
+    }
+
+    /// Write a single register over the bus.
+    fn write_register<'a>(&mut self, register: u8, value: u8) -> Result<(), Error<'a, E>> {
+        self.i2c.write(self.address, &[register, value]).map_err(|e| Error::I2C(e))
+    }
+
+    /// Read a bank A + bank B register pair as a single 16 bit value, relying on
+    /// the chip's sequential auto-increment (IOCON.BANK=0). Bank A ends up in the
+    /// low byte, bank B in the high byte.
+    fn read_register16<'a>(&mut self, register_a: u8) -> Result<u16, Error<'a, E>> {
+        let mut buf = [0u8; 2];
+        self.i2c.write_read(self.address, &[register_a], &mut buf).map_err(|e| Error::I2C(e))?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Write a bank A + bank B register pair in one bus transaction, relying on
+    /// the chip's sequential auto-increment (IOCON.BANK=0). Bank A takes the low
+    /// byte, bank B the high byte.
+    fn write_register16<'a>(&mut self, register_a: u8, value: u16) -> Result<(), Error<'a, E>> {
+        let bytes = value.to_le_bytes();
+        self.i2c.write(self.address, &[register_a, bytes[0], bytes[1]]).map_err(|e| Error::I2C(e))
+    }
+
     /// Change a specific bit in a byte
     fn change_bit(bitmap: u8, pin: &Pin, value: bool) -> u8 {
         if value {
@@ -328,24 +401,24 @@ impl MCP23017 {
     }
 
     /// Set an output pin to a specific value.
-    fn read_and_change_pin<'a>(&self, register: u8, pin: &'a Pin, value: bool, cur_value: Option<u8>) -> Result<u8, Error<'a>> {
+    fn read_and_change_pin<'a>(&mut self, register: u8, pin: &'a Pin, value: bool, cur_value: Option<u8>) -> Result<u8, Error<'a, E>> {
         // if we don't know what the current register's full value is, get it first
         let cur_value = match cur_value {
             Some(cur_value) => cur_value,
-            None => self.i2c.smbus_read_byte(register).map_err(|e| Error::I2C(e))?,
+            None => self.read_register(register)?,
         };
 
         // set the single bit that corresponds to the specific pin within the full register value
-        let new_value = MCP23017::change_bit(cur_value, &pin, value);
+        let new_value = MCP23017::<I2C>::change_bit(cur_value, &pin, value);
 
         // write and return the full register value
-        self.i2c.smbus_write_byte(register, new_value).map_err(|e| Error::I2C(e))?;
+        self.write_register(register, new_value)?;
         Ok(new_value)
     }
 
     /// Used to set the pullUp resistor setting for a pin.
     /// Returns the whole register value.
-    pub fn pull_up<'a>(&self, pin: &'a Pin, value: State) -> Result<u16, Error<'a>> {
+    pub fn pull_up<'a>(&mut self, pin: &'a Pin, value: State) -> Result<u16, Error<'a, E>> {
         let pull = match pin.bank {
             Bank::A => self.read_and_change_pin(GPPUA, pin, value.into(), None)?,
             Bank::B => self.read_and_change_pin(GPPUA, pin, value.into(), None)?,
@@ -356,37 +429,43 @@ impl MCP23017 {
 
     /// Set pin to either input or output mode.
     /// Returns the value of the combined IODIRA and IODIRB registers.
-    pub fn pin_mode<'a>(&mut self, pin: &'a Pin, mode: Mode) -> Result<u16, Error<'a>> {
+    pub fn pin_mode<'a>(&mut self, pin: &'a Pin, mode: Mode) -> Result<u16, Error<'a, E>> {
         let mode = match pin.bank {
             Bank::A => self.read_and_change_pin(IODIRA, pin, mode.into(), None)?,
             Bank::B => self.read_and_change_pin(IODIRB, pin, mode.into(), None)?,
         };
-        
+
         self.direction = pin.apply_u16(self.direction, mode);
         Ok(self.direction)
     }
 
     /// Set an output pin to a specific value.
-    pub fn output<'a>(&self, pin: &'a Pin, value: State) -> Result<u8, Error<'a>>{
+    pub fn output<'a>(&mut self, pin: &'a Pin, value: State) -> Result<u8, Error<'a, E>>{
         if matches!(pin.mode(self.direction), Mode::Output) {
             return Err(Error::WrongMode(pin))
         }
         match pin.bank {
-            Bank::A => self.read_and_change_pin(GPIOA, pin, value.into(), self.i2c.smbus_read_byte(OLATA).ok()),
-            Bank::B => self.read_and_change_pin(GPIOB, pin, value.into(), self.i2c.smbus_read_byte(OLATB).ok()),
+            Bank::A => {
+                let cur = self.read_register(OLATA).ok();
+                self.read_and_change_pin(GPIOA, pin, value.into(), cur)
+            },
+            Bank::B => {
+                let cur = self.read_register(OLATB).ok();
+                self.read_and_change_pin(GPIOB, pin, value.into(), cur)
+            },
         }
     }
 
     /// Read the value of a pin.
-    pub fn input<'a>(&self, pin: &'a Pin) -> Result<State, Error<'a>> {
+    pub fn input<'a>(&mut self, pin: &'a Pin) -> Result<State, Error<'a, E>> {
         if matches!(pin.mode(self.direction), Mode::Input) {
             return Err(Error::WrongMode(pin))
         }
-        
+
         // reads the whole register then compares the value of the specific pin
         let bank_value = match pin.bank {
-            Bank::A => self.i2c.smbus_read_byte(GPIOA).map_err(|e| Error::I2C(e))?,
-            Bank::B => self.i2c.smbus_read_byte(GPIOB).map_err(|e| Error::I2C(e))?,
+            Bank::A => self.read_register(GPIOA)?,
+            Bank::B => self.read_register(GPIOB)?,
         };
 
         Ok(State::from(bank_value & (1 << pin.pin) != 0))
@@ -394,36 +473,85 @@ impl MCP23017 {
 
 
     /// Read the value of a pin regardless of it's mode
-    pub fn current_val<'a>(&self, pin: &'a Pin) -> Result<State, Error<'a>> {
+    pub fn current_val<'a>(&mut self, pin: &'a Pin) -> Result<State, Error<'a, E>> {
         // reads the whole register then compares the value of the specific pin
         let bank_value = match pin.bank {
-            Bank::A => self.i2c.smbus_read_byte(GPIOA).map_err(|e| Error::I2C(e))?,
-            Bank::B => self.i2c.smbus_read_byte(GPIOB).map_err(|e| Error::I2C(e))?,
+            Bank::A => self.read_register(GPIOA)?,
+            Bank::B => self.read_register(GPIOB)?,
         };
 
         Ok(State::from(bank_value & (1 << pin.pin) != 0))
     }
 
+    /// Write all 16 GPIO pins at once. The low byte drives bank A (pins 0-7),
+    /// the high byte bank B (pins 8-15). Both output latches are updated in a
+    /// single bus transaction rather than a read-modify-write per pin.
+    pub fn write_gpio<'a>(&mut self, bits: u16) -> Result<(), Error<'a, E>> {
+        self.write_register16(GPIOA, bits)
+    }
+
+    /// Read all 16 GPIO pins at once. Bank A (pins 0-7) lands in the low byte,
+    /// bank B (pins 8-15) in the high byte.
+    pub fn read_gpio<'a>(&mut self) -> Result<u16, Error<'a, E>> {
+        self.read_register16(GPIOA)
+    }
+
+    /// Set the direction of all 16 pins at once (1 = input, 0 = output), keeping
+    /// the cached direction in sync. The low byte is bank A, the high byte bank B.
+    pub fn set_all_modes<'a>(&mut self, dir: u16) -> Result<(), Error<'a, E>> {
+        self.write_register16(IODIRA, dir)?;
+        self.direction = dir;
+        Ok(())
+    }
+
+    /// Enable or disable the pull-up resistor on all 16 pins at once. The low byte
+    /// is bank A, the high byte bank B.
+    pub fn set_all_pullups<'a>(&mut self, bits: u16) -> Result<(), Error<'a, E>> {
+        self.write_register16(GPPUA, bits)
+    }
+
     /// Configure system interrupt settings.
     /// Mirror - are the int pins mirrored?
     /// Intpol - polarity of the int pin.
-    pub fn config_system_interrupt<'a>(&mut self, mirror: Feature, intpol: State) -> Result<(), Error<'a>>{
+    pub fn config_system_interrupt<'a>(&mut self, mirror: Feature, intpol: State) -> Result<(), Error<'a, E>>{
         // get current register settings
-        let mut register_value = self.i2c.smbus_read_byte(IOCON).map_err(|e| Error::I2C(e))?;
+        let mut register_value = self.read_register(IOCON)?;
         // set mirror bit
-        register_value = MCP23017::change_bit(register_value, &IOCONMIRROR, mirror.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONMIRROR, mirror.into());
 
         // set the intpol bit
-        register_value = MCP23017::change_bit(register_value, &IOCONINTPOL, intpol.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONINTPOL, intpol.into());
 
         // set ODR pin
-        self.i2c.smbus_write_byte(IOCON, register_value).map_err(|e| Error::I2C(e))?;
+        self.write_register(IOCON, register_value)?;
         self.mirrored = mirror;
         Ok(())
     }
 
+    /// Configure the full IOCON register.
+    ///
+    /// Unlike [`MCP23017::config_system_interrupt`], which only touches MIRROR and
+    /// INTPOL, this writes every writeable IOCON bit except BANK, which is left
+    /// as read so the 16 bit register helpers keep working. This is essential when
+    /// chaining several MCP23017s on one INT line into a single host GPIO.
+    pub fn config_iocon<'a>(&mut self, config: IoconConfig) -> Result<(), Error<'a, E>> {
+        // get current register settings
+        let mut register_value = self.read_register(IOCON)?;
+
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONMIRROR, config.mirror.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONODR, config.odr.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONINTPOL, config.intpol.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONSEQOP, config.seqop.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONDISSLW, config.disslw.into());
+        register_value = MCP23017::<I2C>::change_bit(register_value, &IOCONHAEN, config.haen.into());
+
+        self.write_register(IOCON, register_value)?;
+        self.mirrored = config.mirror;
+        Ok(())
+    }
+
     /// Configure interrupt setting for a specific pin. set on or off.
-    pub fn config_pin_interrupt<'a>(&self, pin: &'a Pin, enabled: Feature, compare_mode: Compare, defval: Option<State>) -> Result<(), Error<'a>>{
+    pub fn config_pin_interrupt<'a>(&mut self, pin: &'a Pin, enabled: Feature, compare_mode: Compare, defval: Option<State>) -> Result<(), Error<'a, E>>{
         if matches!(pin.mode(self.direction), Mode::Input) {
             return Err(Error::WrongMode(pin))
         }
@@ -447,104 +575,209 @@ impl MCP23017 {
         Ok(())
     }
 
-    /// Private function to return pin and value from an interrupt
-    fn read_interrupt_register<'a>(&self, port: Bank) -> Result<Option<(Pin, State)>, Error<'a>> {
-        match port {
-            Bank::A => {
-                let interrupted_a = self.i2c.smbus_read_byte(INTFA).map_err(|e| Error::I2C(e))?;
-                if interrupted_a != 0 {
-
-                    let pin = Pin::new((interrupted_a as f32).log2() as u8);
-                    // get the value of the pin
-                    let value_register = self.i2c.smbus_read_byte(INTCAPA).map_err(|e| Error::I2C(e))?;
-                    let value = pin.clone().map(|pin| {let num = pin.pin; (pin, State::from(value_register & (1 << num) != 0))});
-                    Ok(value)
-                } else {
-                    Ok(None)
-                } 
-            },
-            Bank::B => {
-                let interrupted_b = self.i2c.smbus_read_byte(INTFB).map_err(|e| Error::I2C(e))?;
-                if interrupted_b != 0 {
-
-                    let pin = Pin::new((interrupted_b as f32).log2() as u8);
-                    // get the value of the pin
-                    let value_register = self.i2c.smbus_read_byte(INTCAPB).map_err(|e| Error::I2C(e))?;
-                    let value = pin.clone().map(|pin| {let num = pin.pin; (pin, State::from(value_register & (1 << num) != 0))});
-                    Ok(value)
-                } else {
-                    Ok(None)
-                } 
+    /// Private function to return every interrupting pin of a bank and its captured value.
+    fn read_interrupt_register<'a>(&mut self, port: Bank) -> Result<Vec<(Pin, State)>, Error<'a, E>> {
+        let (intf, intcap) = match port {
+            Bank::A => (INTFA, INTCAPA),
+            Bank::B => (INTFB, INTCAPB),
+        };
+
+        let flags = self.read_register(intf)?;
+        if flags == 0 {
+            return Ok(Vec::new());
+        }
+
+        // a single INTCAP read captures every pin of the bank, so read it once and mask per bit
+        let captured = self.read_register(intcap)?;
+        let mut interrupts = Vec::new();
+        for bit in 0..8 {
+            if flags & (1 << bit) != 0 {
+                // map the bank-local bit back to the global 0-15 pin index
+                let offset = match port { Bank::A => 0, Bank::B => 8 };
+                if let Some(pin) = Pin::new(bit + offset) {
+                    interrupts.push((pin, State::from(captured & (1 << bit) != 0)));
+                }
             }
         }
+        Ok(interrupts)
     }
 
     // This function should be called when INTA or INTB is triggered to indicate an interrupt occurred.
-    /// The function determines the pin that caused the interrupt and gets its value.
-    /// The interrupt is cleared.
-    /// Returns pin and the value.
-    pub fn read_interrupt<'a>(self, port: Bank) -> Result<Option<(Pin, State)>, Error<'a>> {
+    /// The function determines every pin that caused the interrupt and gets its captured value.
+    /// The interrupt is cleared by reading INTCAP.
+    /// Returns each interrupting pin and its value.
+    pub fn read_interrupt<'a>(&mut self, port: Bank) -> Result<Vec<(Pin, State)>, Error<'a, E>> {
         // if the mirror is enabled, we don't know what port caused the interrupt, so read both
         match self.mirrored {
             Feature::On => {
-                self.read_interrupt_register(Bank::A).map(|state| {
-                    state.or_else(|| self.read_interrupt_register(Bank::B).unwrap_or(None))
-                })
+                let mut interrupts = self.read_interrupt_register(Bank::A)?;
+                interrupts.extend(self.read_interrupt_register(Bank::B)?);
+                Ok(interrupts)
             },
             Feature::Off => self.read_interrupt_register(port),
         }
     }
 
-    /// Check to see if there is an interrupt pending 3 times in a row (indicating it's stuck) 
-    /// and if needed clear the interrupt without reading values.
-    pub fn clear_interrupts<'a>(&self) -> Result<(), Error<'a>> {
-        if self.i2c.smbus_read_byte(INTFA).map_err(|e| Error::I2C(e))? > 0
-            || self.i2c.smbus_read_byte(INTFB).map_err(|e| Error::I2C(e))? > 0 {
-            
-            for _ in [0..3] {
-                if self.i2c.smbus_read_byte(INTFA).map_err(|e| Error::I2C(e))? == 0
-                    || self.i2c.smbus_read_byte(INTFB).map_err(|e| Error::I2C(e))? == 0 {
-                    return Ok(());
-                } else {
-                    sleep(Duration::from_millis(500));
-                }
-            }
-
-            //  force reset
-            self.i2c.smbus_read_byte(GPIOA).map_err(|e| Error::I2C(e))?;
-            self.i2c.smbus_read_byte(GPIOB).map_err(|e| Error::I2C(e))?;
-            Err(Error::InterruptsForcedClear)
-        } else {
-            Ok(())
-        }
+    /// Read the INTFA/INTFB flag registers once and report which pins, if any,
+    /// have a pending interrupt.
+    ///
+    /// This does not block or sleep, so it is safe to call from an async executor
+    /// or an interrupt handler. The caller owns any retry/backoff timing: poll
+    /// until [`InterruptStatus::pending`] is false, or give up and call
+    /// [`MCP23017::force_clear`] to clear a stuck latch.
+    pub fn poll_interrupts<'a>(&mut self) -> Result<InterruptStatus, Error<'a, E>> {
+        let bank_a = self.read_register(INTFA)?;
+        let bank_b = self.read_register(INTFB)?;
+        Ok(InterruptStatus { bank_a, bank_b })
+    }
+
+    /// Clear a stuck interrupt latch by reading GPIOA/GPIOB, discarding the
+    /// captured values. Does not block or sleep.
+    pub fn force_clear<'a>(&mut self) -> Result<(), Error<'a, E>> {
+        self.read_register(GPIOA)?;
+        self.read_register(GPIOB)?;
+        Ok(())
     }
 
     /// Reset all pins and interrupts
-    pub fn reset<'a>(&self) -> Result<(), Error<'a>> {
-        self.i2c.smbus_write_byte(IODIRA, 0xFF).map_err(|e| Error::I2C(e))?;  // all inputs on port A
-        self.i2c.smbus_write_byte(IODIRB, 0xFF).map_err(|e| Error::I2C(e))?;  // all inputs on port B
+    pub fn reset<'a>(&mut self) -> Result<(), Error<'a, E>> {
+        self.write_register(IODIRA, 0xFF)?;  // all inputs on port A
+        self.write_register(IODIRB, 0xFF)?;  // all inputs on port B
         // make sure the output registers are set to off
-        self.i2c.smbus_write_byte(GPIOA, 0x00).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_write_byte(GPIOB, 0x00).map_err(|e| Error::I2C(e))?;
+        self.write_register(GPIOA, 0x00)?;
+        self.write_register(GPIOB, 0x00)?;
 	    // disable the pull-ups on all ports
-        self.i2c.smbus_write_byte(GPPUA, 0x00).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_write_byte(GPPUB, 0x00).map_err(|e| Error::I2C(e))?;
+        self.write_register(GPPUA, 0x00)?;
+        self.write_register(GPPUB, 0x00)?;
         // clear the IOCON configuration register, which is chip default
-        self.i2c.smbus_write_byte(IOCON, 0x00).map_err(|e| Error::I2C(e))?;
+        self.write_register(IOCON, 0x00)?;
 
-        // disable interrupts on all pins 
-        self.i2c.smbus_write_byte(GPINTENA, 0x00).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_write_byte(GPINTENB, 0x00).map_err(|e| Error::I2C(e))?;
+        // disable interrupts on all pins
+        self.write_register(GPINTENA, 0x00)?;
+        self.write_register(GPINTENB, 0x00)?;
         // interrupt on change register set to compare to previous value by default
-        self.i2c.smbus_write_byte(INTCONA, 0x00).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_write_byte(INTCONB, 0x00).map_err(|e| Error::I2C(e))?;
+        self.write_register(INTCONA, 0x00)?;
+        self.write_register(INTCONB, 0x00)?;
         // interrupt compare value registers
-        self.i2c.smbus_write_byte(DEFVALA, 0x00).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_write_byte(DEFVALB, 0x00).map_err(|e| Error::I2C(e))?;
+        self.write_register(DEFVALA, 0x00)?;
+        self.write_register(DEFVALB, 0x00)?;
         // clear any interrupts to start fresh
-        self.i2c.smbus_read_byte(GPIOA).map_err(|e| Error::I2C(e))?;
-        self.i2c.smbus_read_byte(GPIOB).map_err(|e| Error::I2C(e))?;
+        self.read_register(GPIOA)?;
+        self.read_register(GPIOB)?;
 
         Ok(())
     }
+
+    /// Wrap the expander in a shared-bus handle and hand out an owned
+    /// [`ExpanderPin`] for `pin`. Further pins that share the same bus can be
+    /// split off the returned handle with [`ExpanderPin::split`], the way a HAL
+    /// splits a GPIO port into individual pin structs.
+    pub fn into_pin(self, pin: Pin) -> ExpanderPin<I2C> {
+        ExpanderPin { mcp: Rc::new(RefCell::new(self)), pin }
+    }
+}
+
+/// Owned error returned by the [`embedded_hal::digital`] pin implementations.
+///
+/// Unlike [`Error`] it does not borrow the offending [`Pin`], so it can satisfy
+/// the `'static` digital error bounds.
+#[derive(Debug)]
+pub enum PinError<E> {
+    I2C(E),
+    WrongMode,
+    InterruptsForcedClear,
+}
+
+impl<'a, E> From<Error<'a, E>> for PinError<E> {
+    fn from(err: Error<'a, E>) -> Self {
+        match err {
+            Error::I2C(e) => PinError::I2C(e),
+            Error::WrongMode(_) => PinError::WrongMode,
+            Error::InterruptsForcedClear => PinError::InterruptsForcedClear,
+        }
+    }
+}
+
+impl<E: core::fmt::Debug> embedded_hal::digital::Error for PinError<E> {
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        embedded_hal::digital::ErrorKind::Other
+    }
+}
+
+/// An owned, reference-counted handle to a single pin of a shared [`MCP23017`].
+///
+/// Implements the [`embedded_hal::digital`] pin traits so the pin can be handed
+/// to drivers that expect GPIO objects. Cloning the underlying bus handle lets
+/// several pins share one expander.
+pub struct ExpanderPin<I2C> {
+    mcp: Rc<RefCell<MCP23017<I2C>>>,
+    pin: Pin,
+}
+
+impl<I2C, E> ExpanderPin<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Split another pin off the same shared bus.
+    pub fn split(&self, pin: Pin) -> ExpanderPin<I2C> {
+        ExpanderPin { mcp: Rc::clone(&self.mcp), pin }
+    }
+}
+
+impl<I2C, E> embedded_hal::digital::ErrorType for ExpanderPin<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    type Error = PinError<E>;
+}
+
+impl<I2C, E> embedded_hal::digital::OutputPin for ExpanderPin<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.mcp.borrow_mut().output(&self.pin, State::High)?;
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.mcp.borrow_mut().output(&self.pin, State::Low)?;
+        Ok(())
+    }
+}
+
+impl<I2C, E> embedded_hal::digital::StatefulOutputPin for ExpanderPin<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        // read back the output latch rather than the live pin value
+        let olat = match self.pin.bank {
+            Bank::A => OLATA,
+            Bank::B => OLATB,
+        };
+        let value = self.mcp.borrow_mut().read_register(olat)?;
+        Ok(value & (1 << self.pin.pin) != 0)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_set_high()?)
+    }
+}
+
+impl<I2C, E> embedded_hal::digital::InputPin for ExpanderPin<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: core::fmt::Debug,
+{
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(matches!(self.mcp.borrow_mut().current_val(&self.pin)?, State::High))
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.is_high()?)
+    }
 }
\ No newline at end of file